@@ -1,12 +1,48 @@
 mod packet;
-use std::fs::File;
-use std::io::Read;
-use std::net::UdpSocket;
+mod resolve;
+mod server;
+use std::io::{Read, Write};
+use std::net::{TcpStream, UdpSocket};
+
+use packet::packet::{BytePacketBuffer, DnsPacket, PacketBuffer, VectorPacketBuffer};
 
 type Error = Box<dyn std::error::Error>;
 type Result<T> = std::result::Result<T, Error>;
 
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        // `rust_dns serve` runs the UDP server loop, answering incoming
+        // queries by recursing from the root servers.
+        Some("serve") => return server::run(2053),
+
+        // `rust_dns resolve <name> [type]` drives the resolver directly,
+        // without going through the server loop.
+        Some("resolve") => {
+            let qname = args.get(2).ok_or("usage: rust_dns resolve <name> [type]")?;
+            let qtype = match args.get(3).map(String::as_str) {
+                Some("NS") => packet::query::QueryType::NS,
+                Some("CNAME") => packet::query::QueryType::CNAME,
+                Some("MX") => packet::query::QueryType::MX,
+                Some("AAAA") => packet::query::QueryType::AAAA,
+                _ => packet::query::QueryType::A,
+            };
+
+            let response = resolve::resolve(qname, qtype)?;
+            println!("{:#?}", response.header);
+            for rec in response.answers {
+                println!("{:#?}", rec);
+            }
+
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // No subcommand: fall through to the original one-shot forwarder demo
+    // below, which queries a single upstream directly.
+
     // Perform an A query for google.com
     let qname = "cloudflare.com";
     let qtype = packet::query::QueryType::A;
@@ -19,7 +55,7 @@ fn main() -> Result<()> {
 
     // Build our query packet. It's important that we remember to set the
     // `recursion_desired` flag. As noted earlier, the packet id is arbitrary.
-    let mut dns_packet = packet::packet::DnsPacket::new();
+    let mut dns_packet = DnsPacket::new();
 
     dns_packet.header.id = 6666;
     dns_packet.header.questions = 1;
@@ -29,20 +65,38 @@ fn main() -> Result<()> {
         .push(packet::question::DnsQuestion::new(qname.to_string(), qtype));
 
     // Use our new write method to write the packet to a buffer...
-    let mut req_buffer = packet::packet::BytePacketBuffer::new();
+    let mut req_buffer = BytePacketBuffer::new();
     dns_packet.write(&mut req_buffer)?;
 
+    // EDNS(0): append an OPT pseudo-record to the additional section so the
+    // server knows it's allowed to reply with more than the default 512
+    // UDP bytes, instead of silently truncating a large answer.
+    write_edns_opt(&mut req_buffer, 4096)?;
+
     // ...and send it off to the server using our socket:
     socket.send_to(&req_buffer.buf[0..req_buffer.pos], server)?;
 
-    // To prepare for receiving the response, we'll create a new `BytePacketBuffer`,
-    // and ask the socket to write the response directly into our buffer.
-    let mut res_buffer = packet::packet::BytePacketBuffer::new();
-    socket.recv_from(&mut res_buffer.buf)?;
+    // To prepare for receiving the response, we read into a buffer sized
+    // for the payload we just advertised rather than the 512-byte UDP
+    // default, since the server is now allowed to use the extra room.
+    let mut res_data = vec![0u8; 4096];
+    let (len, _) = socket.recv_from(&mut res_data)?;
+    res_data.truncate(len);
+
+    let mut res_buffer = VectorPacketBuffer::new();
+    res_buffer.buf = res_data;
+    let mut res_packet = DnsPacket::from_buffer(&mut res_buffer)?;
+
+    // The server set the TC bit because the UDP reply couldn't hold the
+    // full answer, so re-issue the same query over TCP, where there's no
+    // size ceiling.
+    if res_packet.header.truncated_message {
+        let mut tcp_buffer = send_tcp_query(&req_buffer.buf[0..req_buffer.pos], server)?;
+        res_packet = DnsPacket::from_buffer(&mut tcp_buffer)?;
+    }
 
     // As per the previous section, `DnsPacket::from_buffer()` is then used to
     // actually parse the packet after which we can print the response.
-    let res_packet = packet::packet::DnsPacket::from_buffer(&mut res_buffer)?;
     println!("{:#?}", res_packet.header);
 
     for q in res_packet.questions {
@@ -59,4 +113,49 @@ fn main() -> Result<()> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// write_edns_opt appends an EDNS(0) OPT pseudo-record (type 41) to whatever
+// buffer a packet was just written to, and bumps ARCOUNT in the header to
+// account for it. The CLASS field of an OPT record isn't a real class; by
+// convention it carries the UDP payload size the sender is willing to
+// receive.
+fn write_edns_opt(buffer: &mut dyn PacketBuffer, udp_payload_size: u16) -> Result<()> {
+    buffer.write_u8(0)?; // NAME: root
+    buffer.write_u16(41)?; // TYPE: OPT
+    buffer.write_u16(udp_payload_size)?; // CLASS: advertised UDP payload size
+    buffer.write_u32(0)?; // TTL: extended RCODE + flags, unused here
+    buffer.write_u16(0)?; // RDLENGTH: no options
+
+    // ARCOUNT lives at byte offset 10 in the header; patch it now that the
+    // additional section has one more record than DnsPacket::write knew
+    // about when it wrote the header.
+    let arcount_pos = 10;
+    let arcount = ((buffer.get(arcount_pos)? as u16) << 8) | (buffer.get(arcount_pos + 1)? as u16);
+    buffer.set_u16(arcount_pos, arcount + 1)?;
+
+    Ok(())
+}
+
+// send_tcp_query re-issues a previously written query over TCP, where
+// messages are framed with a leading 2-byte big-endian length prefix
+// instead of being one-datagram-per-message.
+fn send_tcp_query(query: &[u8], server: (&str, u16)) -> Result<VectorPacketBuffer> {
+    let mut stream = TcpStream::connect(server)?;
+
+    let len = query.len() as u16;
+    stream.write_all(&[(len >> 8) as u8, (len & 0xFF) as u8])?;
+    stream.write_all(query)?;
+
+    let mut len_buf = [0u8; 2];
+    stream.read_exact(&mut len_buf)?;
+    let res_len = ((len_buf[0] as usize) << 8) | (len_buf[1] as usize);
+
+    let mut res_data = vec![0u8; res_len];
+    stream.read_exact(&mut res_data)?;
+
+    let mut res_buffer = VectorPacketBuffer::new();
+    res_buffer.buf = res_data;
+
+    Ok(res_buffer)
+}
@@ -1,4 +1,56 @@
-use super::{result::ResultCode, packet::BytePacketBuffer};
+use bitflags::bitflags;
+
+use super::{packet::PacketBuffer, result::ResultCode};
+
+bitflags! {
+    /// The simple on/off bits of the 16-bit flags word. Opcode and
+    /// RCODE aren't modeled here since they're multi-bit fields rather
+    /// than flags - those get their own typed representation below. The
+    /// one truly reserved bit of the historical 3-bit "Z" field (the other
+    /// two were repurposed as AD/CD) isn't given a name since nothing
+    /// should ever set it.
+    pub struct Flags: u16 {
+        const RESPONSE             = 0b1000_0000_0000_0000;
+        const AUTHORITATIVE        = 0b0000_0100_0000_0000;
+        const TRUNCATED            = 0b0000_0010_0000_0000;
+        const RECURSION_DESIRED    = 0b0000_0001_0000_0000;
+        const RECURSION_AVAILABLE  = 0b0000_0000_1000_0000;
+        const AUTHENTIC_DATA       = 0b0000_0000_0010_0000;
+        const CHECK_DISABLED       = 0b0000_0000_0001_0000;
+    }
+}
+
+// The 4-bit Opcode field. UNKNOWN carries through any value we don't
+// recognize instead of silently clamping it.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Opcode {
+    QUERY,
+    IQUERY,
+    STATUS,
+    UNKNOWN(u8),
+}
+
+impl Opcode {
+    pub fn from_num(num: u8) -> Opcode {
+        match num {
+            0 => Opcode::QUERY,
+            1 => Opcode::IQUERY,
+            2 => Opcode::STATUS,
+            num => Opcode::UNKNOWN(num),
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_num(&self) -> u8 {
+        match *self {
+            Opcode::QUERY => 0,
+            Opcode::IQUERY => 1,
+            Opcode::STATUS => 2,
+            Opcode::UNKNOWN(num) => num,
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct DnsHeader {
@@ -7,7 +59,7 @@ pub struct DnsHeader {
     pub recursion_desired: bool,    // 1 bit
     pub truncated_message: bool,    // 1 bit
     pub authoritative_answer: bool, // 1 bit
-    pub opcode: u8,                 // 4 bits
+    pub opcode: Opcode,             // 4 bits
     pub response: bool,             // 1 bit
 
     pub rescode: super::result::ResultCode,       // 4 bits
@@ -30,7 +82,7 @@ impl DnsHeader {
             recursion_desired: false,
             truncated_message: false,
             authoritative_answer: false,
-            opcode: 0,
+            opcode: Opcode::QUERY,
             response: false,
 
             rescode: super::result::ResultCode::NOERROR,
@@ -49,7 +101,7 @@ impl DnsHeader {
     // read wants to take a BytePacketBuffer and deserialize it into this DnsHeader.
     // this allows us to make sense of the incoming TCP byte stream
     /*
-     
+
                                     1  1  1  1  1  1
       0  1  2  3  4  5  6  7  8  9  0  1  2  3  4  5
     +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
@@ -67,74 +119,33 @@ impl DnsHeader {
     +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
 
      */
-    pub fn read(&mut self, buffer: &mut super::packet::BytePacketBuffer) -> super::Result<()> {
-        // We know the header structure, so the bit twiddling should be of no surprise, but
-        // I'll add comments for clarity
-
+    pub fn read(&mut self, buffer: &mut dyn PacketBuffer) -> super::Result<()> {
         // the first 16 bits are the random ID specified in the RFC
         self.id = buffer.read_u16()?;
 
-        // the remaining 16 bits are the flags, we can get clever for parsing them
-        let flags = buffer.read_u16()?;
+        // the remaining 16 bits are the flags. Pull the named bits out via
+        // the Flags type instead of hand-shifting each one.
+        let flags_num = buffer.read_u16()?;
+        let flags = Flags::from_bits_truncate(flags_num);
 
-        // Let's load them in 2 chunks. 
-        // this confused me at first, but I misread the ASCII diagram. The MSB is starting at Z -> 
-        // RCODE -> QR... with that said:
+        self.recursion_desired = flags.contains(Flags::RECURSION_DESIRED);
+        self.truncated_message = flags.contains(Flags::TRUNCATED);
+        self.authoritative_answer = flags.contains(Flags::AUTHORITATIVE);
+        self.response = flags.contains(Flags::RESPONSE);
 
-        // flags is 16 bits, if we right shift 8 bits, we throw away RA, Z and RCode
-        let a = (flags >> 8) as u8;
+        // Opcode lives in the 4 bits between QR and AA.
+        self.opcode = Opcode::from_num(((flags_num >> 11) & 0x0F) as u8);
 
-        // b is just taking the first 8 bits (RA, Z RCODE)
-        let b = (flags & 0xFF) as u8;
+        // RCODE is the bottom 4 bits of the word.
+        self.rescode = ResultCode::from_num((flags_num & 0x0F) as u8);
 
-        // the << 0 is for reading clarity, but equivalent to a AND 1
-        // basically we want to eval the flag bits to see if they're flipped off or on
-        // remember, the AA, TC, RD flags are the current LSBs
-        
-        // imagine we have AA TC and RD being 1, 1, 0
-        // so basically this focuses on the LSB and nothing else
-        // 10110110 <-- RD
-        // 00000001
+        self.checking_disabled = flags.contains(Flags::CHECK_DISABLED);
+        self.authed_data = flags.contains(Flags::AUTHENTIC_DATA);
 
-        // RD: see above -> output = 0
-        self.recursion_desired = (a & (1 << 0)) > 0;
+        // The one bit of the historical "Z" field that's still reserved.
+        self.z = (flags_num & 0b0000_0000_0100_0000) > 0;
 
-        // 10110110 <-- RD
-        // 00000010
-        // using above picture, this shift 1 over so we have 2 in binary (10)
-        // IE TC is 1
-        self.truncated_message = (a & (1 << 1)) > 0;
-
-        // 10110110 <-- RD
-        // 00000100
-        // using above picture, this shift 1 over so we have 4 in binary (100)
-        // IE AA is 1
-        self.authoritative_answer = (a & (1 << 2)) > 0;
-
-        // opcode is interesting. We know we have the 8 bits as LSB, but we want to focus on the 4
-        // bits of the opcode. well.. AA, TC and RD are only 3 bits, so just discard them with 
-        // a 3 bit right shift. 0x0F is Hex for 0b1111
-        // we set the AND RHS to 0x0F because we can light up which bit is flipped for the opcode
-        self.opcode = (a >> 3) & 0x0F;
-
-        // response is easy because we reach wayyyy over the MSB of the.. well current LSB 8 bits
-        // this is a single bit and the total current buffer is 8 bits, so discard the 7 LSB bits
-        // and AND with 1
-        self.response = (a & (1 << 7)) > 0;
-
-        // nice little fuckery to AND hex to generate int
-        self.rescode = ResultCode::from_num(b & 0x0F);
-
-
-        self.checking_disabled = (b & (1 << 4)) > 0;
-        self.authed_data = (b & (1 << 5)) > 0;
-
-        // just needs to be greater than 0
-        self.z = (b & (1 << 6)) > 0;
-
-        // 1000_0000
-        // discard 7 bits to focus on RA
-        self.recursion_available = (b & (1 << 7)) > 0;
+        self.recursion_available = flags.contains(Flags::RECURSION_AVAILABLE);
 
         self.questions = buffer.read_u16()?;
         self.answers = buffer.read_u16()?;
@@ -144,25 +155,24 @@ impl DnsHeader {
         Ok(())
     }
 
-
-    pub fn write(&self, buffer: &mut BytePacketBuffer) -> super::Result<()> {
+    pub fn write(&self, buffer: &mut dyn PacketBuffer) -> super::Result<()> {
         buffer.write_u16(self.id)?;
 
-        buffer.write_u8(
-            (self.recursion_desired as u8)
-                | ((self.truncated_message as u8) << 1)
-                | ((self.authoritative_answer as u8) << 2)
-                | (self.opcode << 3)
-                | ((self.response as u8) << 7) as u8,
-        )?;
-
-        buffer.write_u8(
-            (self.rescode as u8)
-                | ((self.checking_disabled as u8) << 4)
-                | ((self.authed_data as u8) << 5)
-                | ((self.z as u8) << 6)
-                | ((self.recursion_available as u8) << 7),
-        )?;
+        let mut flags = Flags::empty();
+        flags.set(Flags::RESPONSE, self.response);
+        flags.set(Flags::AUTHORITATIVE, self.authoritative_answer);
+        flags.set(Flags::TRUNCATED, self.truncated_message);
+        flags.set(Flags::RECURSION_DESIRED, self.recursion_desired);
+        flags.set(Flags::RECURSION_AVAILABLE, self.recursion_available);
+        flags.set(Flags::AUTHENTIC_DATA, self.authed_data);
+        flags.set(Flags::CHECK_DISABLED, self.checking_disabled);
+
+        let mut flags_num = flags.bits();
+        flags_num |= (self.opcode.to_num() as u16 & 0x0F) << 11;
+        flags_num |= (self.z as u16) << 6;
+        flags_num |= self.rescode.to_num() as u16 & 0x0F;
+
+        buffer.write_u16(flags_num)?;
 
         buffer.write_u16(self.questions)?;
         buffer.write_u16(self.answers)?;
@@ -171,4 +181,4 @@ impl DnsHeader {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
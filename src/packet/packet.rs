@@ -1,86 +1,44 @@
-pub struct BytePacketBuffer {
-    pub buf: [u8; 512],
-    pub pos: usize,
-}
-
-impl BytePacketBuffer {
-    // new, zero-initialized byte packet
-    pub fn new() -> BytePacketBuffer {
-        BytePacketBuffer {
-            buf: [0; 512],
-            pos: 0,
-        }
-    }
-    
-    // pos will get the current position of the bytepacket
-    pub fn pos(&self) -> usize {
-        self.pos
-    }
-
-    // modifies the reference by pushing the buffer
-    // position forward a certain no. of steps
-    pub fn step(&mut self, steps: usize) -> super::Result<()> {
-        self.pos += steps;
-        Ok(())
-    }
-
-        /// Change the buffer position
-        pub fn seek(&mut self, pos: usize) -> super::Result<()> {
-            self.pos = pos;
-    
-            Ok(())
-        }
-
-
-
-    // read will read a single byte and push the buffer
-    // position one step forward
-    pub fn read(&mut self) -> super::Result<u8> {
-        if self.pos >= 512 {
-            // todo: research the .into syntax
-            return Err("end of buffer".into());
-        }
-
-        let res = self.buf[self.pos];
-        self.pos += 1;
-
-        Ok(res)
-    }
-
-    // get a single byte, without changing the position of the buffer
-    pub fn get(&mut self, pos: usize) -> super::Result<u8> {
-        if pos >= 512 {
-            return Err("end of buffer".into());
-        }
-        Ok(self.buf[pos])
-    }
-
-    // get_range will yield a range of bytes from a starting position
-    pub fn get_range(&mut self, start: usize, len: usize) -> super::Result<&[u8]> {
-        if start + len >= 512 {
-            return Err("end of buffer".into())
-        }
-        Ok(&self.buf[start..start + len as usize])
-    }
+use std::collections::HashMap;
+
+// PacketBuffer is the common read/write surface that both the fixed-size
+// UDP buffer and the growable TCP buffer implement. Pulling it out as a
+// trait means DnsHeader/DnsQuestion/DnsRecord/DnsPacket can read and write
+// either buffer without caring which one they got.
+pub trait PacketBuffer {
+    fn read(&mut self) -> super::Result<u8>;
+    fn get(&mut self, pos: usize) -> super::Result<u8>;
+    fn get_range(&mut self, start: usize, len: usize) -> super::Result<&[u8]>;
+    fn set(&mut self, pos: usize, val: u8) -> super::Result<()>;
+    fn step(&mut self, steps: usize) -> super::Result<()>;
+    fn seek(&mut self, pos: usize) -> super::Result<()>;
+    fn pos(&self) -> usize;
+    fn write(&mut self, val: u8) -> super::Result<()>;
+
+    // find_label/save_label back the name-compression table used by
+    // write_qname. Each implementor is free to decide what "can't afford
+    // to remember this" means (the fixed buffer never needs to, the
+    // growable one always can).
+    fn find_label(&self, suffix: &str) -> Option<usize>;
+    fn save_label(&mut self, suffix: &str, pos: usize);
 
     // read_u16 will read 2 bytes and step forward 2 steps
-    pub fn read_u16(&mut self) -> super::Result<u16> {
+    fn read_u16(&mut self) -> super::Result<u16> {
         let res = ((self.read()? as u16) << 8) | (self.read()? as u16);
         Ok(res)
     }
 
     // read_u32 will read 2 bytes and step forward 2 steps
-    pub fn read_u32(&mut self) -> super::Result<u32> {
-        let res = ((self.read()? as u32) << 24) 
-                    | ((self.read()? as u32) << 16)
-                    | ((self.read()? as u32) << 8)
-                    | ((self.read()? as u32) << 0);
+    fn read_u32(&mut self) -> super::Result<u32> {
+        let res = ((self.read()? as u32) << 24)
+            | ((self.read()? as u32) << 16)
+            | ((self.read()? as u32) << 8)
+            | (self.read()? as u32);
 
         Ok(res)
     }
 
     // let's read in a query name
-    pub fn read_qname(&mut self, outstr: &mut String) -> super::Result<()> {
+    fn read_qname(&mut self, outstr: &mut String) -> super::Result<()> {
         let mut pos = self.pos();
 
         let mut jumped = false;
@@ -97,20 +55,31 @@ impl BytePacketBuffer {
             let len = self.get(pos)?;
 
             // load the next byte into memory. We want to see if message
-            // compression is being used, so we need to check if the 2 MSBs are 
-            // set to 1. 
+            // compression is being used, so we need to check if the 2 MSBs are
+            // set to 1.
             // len = 1 byte
             // 0xC0 = 1 byte which is 0b11000000 in binary
             // logical AND proves that the 2 MSBs are set to 11, which means
             // we need to follow the jump
             if (len & 0xC0) == 0xC0 {
+                let b2 = self.get(pos + 1)? as u16;
+                let offset = ((((len as u16) ^ 0xC0) << 8) | b2) as usize;
+
+                // A pointer may only reference data that comes strictly
+                // before it on the wire. Anything pointing forward or at
+                // itself can't be legitimate compression - it's either a
+                // crafted packet trying to make us spin (a pointer cycle)
+                // or waste work chasing data that hasn't been read yet -
+                // so bail out immediately instead of following it.
+                if offset >= pos {
+                    return Err("compression pointer does not point backward".into());
+                }
+
                 if !jumped {
-                    self.seek(pos + 2);
+                    self.seek(pos + 2)?;
                 }
 
-                let b2 = self.get(pos + 1)? as u16;
-                let offset = (((len as u16) ^ 0xC0) << 8) | b2;
-                pos = offset as usize;
+                pos = offset;
 
                 jumped = true;
                 jumps_performed += 1;
@@ -130,6 +99,14 @@ impl BytePacketBuffer {
                     break;
                 }
 
+                // A full domain name is capped at 255 bytes on the wire, so
+                // there's no legitimate reason for the decoded string to
+                // grow past that - stop an attacker from using pointers to
+                // inflate the output far beyond what a real name could be.
+                if outstr.len() + delim.len() + len as usize > 255 {
+                    return Err("name exceeds the 255 byte limit".into());
+                }
+
                 // Append the delimiter to our output buffer first.
                 outstr.push_str(delim);
 
@@ -152,39 +129,59 @@ impl BytePacketBuffer {
         Ok(())
     }
 
-    pub fn write(&mut self, val: u8) -> super::Result<()> {
-        if self.pos >= 512 {
-            return Err("end of buffer".into())
-        }
-        self.buf[self.pos] = val;
-        self.pos += 1;
+    // set_u16 is set's big-endian, 2-byte counterpart.
+    fn set_u16(&mut self, pos: usize, val: u16) -> super::Result<()> {
+        self.set(pos, (val >> 8) as u8)?;
+        self.set(pos + 1, (val & 0xFF) as u8)?;
+
         Ok(())
     }
 
-    pub fn write_u8(&mut self, val: u8) -> super::Result<()> {
+    fn write_u8(&mut self, val: u8) -> super::Result<()> {
         self.write(val)?;
 
         Ok(())
     }
 
-    pub fn write_u16(&mut self, val: u16) -> super::Result<()> {
+    fn write_u16(&mut self, val: u16) -> super::Result<()> {
         self.write((val >> 8) as u8)?;
         self.write((val & 0xFF) as u8)?;
 
         Ok(())
     }
 
-    pub fn write_u32(&mut self, val: u32) -> super::Result<()> {
+    fn write_u32(&mut self, val: u32) -> super::Result<()> {
         self.write(((val >> 24) & 0xFF) as u8)?;
         self.write(((val >> 16) & 0xFF) as u8)?;
         self.write(((val >> 8) & 0xFF) as u8)?;
-        self.write(((val >> 0) & 0xFF) as u8)?;
+        self.write((val & 0xFF) as u8)?;
 
         Ok(())
     }
 
-    pub fn write_qname(&mut self, qname: &str) -> super::Result<()> {
-        for label in qname.split('.') {
+    fn write_qname(&mut self, qname: &str) -> super::Result<()> {
+        let labels: Vec<&str> = qname.split('.').collect();
+
+        for i in 0..labels.len() {
+            // The suffix starting at this label, e.g. for "www.example.com"
+            // that's "www.example.com", then "example.com", then "com".
+            let suffix = labels[i..].join(".");
+
+            if let Some(prev_pos) = self.find_label(&suffix) {
+                // We've already written this exact suffix somewhere earlier
+                // in the packet, so point back at it instead of repeating
+                // the labels. The offset only has 14 bits to live in, so
+                // anything further back than that has to be spelled out.
+                if prev_pos < 0x3FFF {
+                    let pointer = 0xC000 | (prev_pos as u16);
+                    self.write_u16(pointer)?;
+                    return Ok(());
+                }
+            }
+
+            self.save_label(&suffix, self.pos());
+
+            let label = labels[i];
             let len = label.len();
             if len > 0x3f {
                 return Err("Single label exceeds 63 characters of length".into());
@@ -196,11 +193,396 @@ impl BytePacketBuffer {
             }
         }
 
+        // Only reached if we made it through every label without finding a
+        // suffix to point at, so the name needs an explicit terminator.
         self.write_u8(0)?;
 
         Ok(())
     }
 
+    // write_qname_unicode is write_qname's IDNA-aware front door: it runs
+    // `qname` through ToASCII first, so a user-supplied Unicode domain
+    // like "müller.de" goes out on the wire as its "xn--" A-label form
+    // instead of raw UTF-8. Labels already in ASCII (including ones
+    // already in xn-- form) pass through apart from being lowercased, and the 63-byte label
+    // limit in write_qname is checked against this encoded form.
+    fn write_qname_unicode(&mut self, qname: &str) -> super::Result<()> {
+        let ascii_name = idna::domain_to_ascii(qname)
+            .map_err(|e| format!("invalid domain name {:?}: {:?}", qname, e))?;
+
+        self.write_qname(&ascii_name)
+    }
+
+    // read_qname_unicode is read_qname plus a ToUnicode decode, for
+    // callers that want a human-readable name back instead of the wire
+    // "xn--" form. Nothing in this crate calls it yet - it's here for
+    // whichever caller first needs to show a name to a human instead of
+    // just comparing/forwarding it on the wire.
+    #[allow(dead_code)]
+    fn read_qname_unicode(&mut self, outstr: &mut String) -> super::Result<()> {
+        let mut ascii_name = String::new();
+        self.read_qname(&mut ascii_name)?;
+
+        // ToUnicode is presentation-only and, per the IDNA spec, never
+        // hard-fails - a label it can't make sense of is just left alone -
+        // so the error half of the tuple is safe to ignore here.
+        let (unicode_name, _) = idna::domain_to_unicode(&ascii_name);
+        outstr.push_str(&unicode_name);
+
+        Ok(())
+    }
+}
+
+pub struct BytePacketBuffer {
+    pub buf: [u8; 512],
+    pub pos: usize,
+
+    // Tracks the byte offset at which each fully-qualified name suffix was
+    // first written, so write_qname can point back at it instead of
+    // repeating the labels (RFC 1035 message compression).
+    names: HashMap<String, usize>,
+}
+
+impl BytePacketBuffer {
+    // new, zero-initialized byte packet
+    pub fn new() -> BytePacketBuffer {
+        BytePacketBuffer {
+            buf: [0; 512],
+            pos: 0,
+            names: HashMap::new(),
+        }
+    }
+}
+
+impl PacketBuffer for BytePacketBuffer {
+    // pos will get the current position of the bytepacket
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    // modifies the reference by pushing the buffer
+    // position forward a certain no. of steps
+    fn step(&mut self, steps: usize) -> super::Result<()> {
+        self.pos += steps;
+        Ok(())
+    }
+
+    /// Change the buffer position
+    fn seek(&mut self, pos: usize) -> super::Result<()> {
+        self.pos = pos;
+
+        Ok(())
+    }
+
+    // read will read a single byte and push the buffer
+    // position one step forward
+    fn read(&mut self) -> super::Result<u8> {
+        if self.pos >= 512 {
+            // todo: research the .into syntax
+            return Err("end of buffer".into());
+        }
+
+        let res = self.buf[self.pos];
+        self.pos += 1;
+
+        Ok(res)
+    }
+
+    // get a single byte, without changing the position of the buffer
+    fn get(&mut self, pos: usize) -> super::Result<u8> {
+        if pos >= 512 {
+            return Err("end of buffer".into());
+        }
+        Ok(self.buf[pos])
+    }
+
+    // get_range will yield a range of bytes from a starting position
+    fn get_range(&mut self, start: usize, len: usize) -> super::Result<&[u8]> {
+        if start + len > 512 {
+            return Err("end of buffer".into());
+        }
+        Ok(&self.buf[start..start + len])
+    }
+
+    // set overwrites a single byte that's already been written, without
+    // touching the current position. Used to patch a length/pointer field
+    // after the fact.
+    fn set(&mut self, pos: usize, val: u8) -> super::Result<()> {
+        if pos >= 512 {
+            return Err("end of buffer".into());
+        }
+        self.buf[pos] = val;
+        Ok(())
+    }
+
+    fn write(&mut self, val: u8) -> super::Result<()> {
+        if self.pos >= 512 {
+            return Err("end of buffer".into());
+        }
+        self.buf[self.pos] = val;
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn find_label(&self, suffix: &str) -> Option<usize> {
+        self.names.get(suffix).copied()
+    }
+
+    fn save_label(&mut self, suffix: &str, pos: usize) {
+        self.names.insert(suffix.to_string(), pos);
+    }
+}
+
+// VectorPacketBuffer is the growable counterpart to BytePacketBuffer. It
+// backs the TCP transport, where messages aren't capped at 512 bytes, and
+// grows on write instead of erroring out at a fixed size.
+pub struct VectorPacketBuffer {
+    pub buf: Vec<u8>,
+    pub pos: usize,
+
+    names: HashMap<String, usize>,
+}
+
+impl VectorPacketBuffer {
+    pub fn new() -> VectorPacketBuffer {
+        VectorPacketBuffer {
+            buf: Vec::new(),
+            pos: 0,
+            names: HashMap::new(),
+        }
+    }
+}
+
+impl PacketBuffer for VectorPacketBuffer {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    fn step(&mut self, steps: usize) -> super::Result<()> {
+        self.pos += steps;
+        Ok(())
+    }
+
+    fn seek(&mut self, pos: usize) -> super::Result<()> {
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn read(&mut self) -> super::Result<u8> {
+        if self.pos >= self.buf.len() {
+            return Err("end of buffer".into());
+        }
+
+        let res = self.buf[self.pos];
+        self.pos += 1;
+
+        Ok(res)
+    }
+
+    fn get(&mut self, pos: usize) -> super::Result<u8> {
+        if pos >= self.buf.len() {
+            return Err("end of buffer".into());
+        }
+        Ok(self.buf[pos])
+    }
+
+    fn get_range(&mut self, start: usize, len: usize) -> super::Result<&[u8]> {
+        if start + len > self.buf.len() {
+            return Err("end of buffer".into());
+        }
+        Ok(&self.buf[start..start + len])
+    }
+
+    fn set(&mut self, pos: usize, val: u8) -> super::Result<()> {
+        if pos >= self.buf.len() {
+            return Err("end of buffer".into());
+        }
+        self.buf[pos] = val;
+        Ok(())
+    }
+
+    // write grows the backing Vec instead of erroring out, which is the
+    // whole point of this buffer: there's no 512-byte ceiling. A seek/step
+    // past the current end means there's a gap to fill, not just one byte
+    // to append, so push until we've caught up rather than indexing past
+    // the Vec's length and panicking.
+    fn write(&mut self, val: u8) -> super::Result<()> {
+        if self.pos >= self.buf.len() {
+            self.buf.resize(self.pos, 0);
+            self.buf.push(val);
+        } else {
+            self.buf[self.pos] = val;
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn find_label(&self, suffix: &str) -> Option<usize> {
+        self.names.get(suffix).copied()
+    }
+
+    fn save_label(&mut self, suffix: &str, pos: usize) {
+        self.names.insert(suffix.to_string(), pos);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_qname_compresses_repeated_suffixes() {
+        let mut buffer = BytePacketBuffer::new();
+
+        buffer.write_qname("www.example.com").unwrap();
+        let second_name_pos = buffer.pos();
+        buffer.write_qname("example.com").unwrap();
+        let after_second_name = buffer.pos();
+
+        buffer.seek(0).unwrap();
+        let mut first = String::new();
+        buffer.read_qname(&mut first).unwrap();
+        assert_eq!(first, "www.example.com");
+
+        buffer.seek(second_name_pos).unwrap();
+        let mut second = String::new();
+        buffer.read_qname(&mut second).unwrap();
+        assert_eq!(second, "example.com");
+
+        // "example.com" was already on the wire, so it should have been
+        // compressed down to a single 2 byte pointer.
+        assert_eq!(after_second_name - second_name_pos, 2);
+    }
+
+    #[test]
+    fn write_qname_round_trips_without_compression_opportunity() {
+        let mut buffer = BytePacketBuffer::new();
+        buffer.write_qname("example.org").unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut name = String::new();
+        buffer.read_qname(&mut name).unwrap();
+        assert_eq!(name, "example.org");
+    }
+
+    #[test]
+    fn write_qname_compresses_partial_suffix() {
+        let mut buffer = BytePacketBuffer::new();
+
+        buffer.write_qname("example.com").unwrap();
+        let second_name_pos = buffer.pos();
+        // Shares only the "com" suffix with the first name, so the label
+        // "mail" should still be written out before the pointer.
+        buffer.write_qname("mail.com").unwrap();
+
+        buffer.seek(second_name_pos).unwrap();
+        let mut second = String::new();
+        buffer.read_qname(&mut second).unwrap();
+        assert_eq!(second, "mail.com");
+    }
+
+    #[test]
+    fn read_qname_rejects_forward_pointer() {
+        let mut buffer = BytePacketBuffer::new();
+        // A pointer at position 0 claiming to point at position 4, which
+        // hasn't been read yet.
+        buffer.buf[0] = 0xC0;
+        buffer.buf[1] = 0x04;
+
+        let mut name = String::new();
+        assert!(buffer.read_qname(&mut name).is_err());
+    }
+
+    #[test]
+    fn read_qname_rejects_self_referential_pointer() {
+        let mut buffer = BytePacketBuffer::new();
+        // A pointer at position 0 pointing right back at position 0.
+        buffer.buf[0] = 0xC0;
+        buffer.buf[1] = 0x00;
+
+        let mut name = String::new();
+        assert!(buffer.read_qname(&mut name).is_err());
+    }
+
+    #[test]
+    fn read_qname_rejects_oversized_name() {
+        let mut buffer = BytePacketBuffer::new();
+
+        // 60-byte labels joined by single-byte length prefixes; eight of
+        // them blows well past the 255 byte name limit before the
+        // terminator is ever reached.
+        for _ in 0..8 {
+            buffer.write_u8(60).unwrap();
+            for _ in 0..60 {
+                buffer.write_u8(b'a').unwrap();
+            }
+        }
+        buffer.write_u8(0).unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut name = String::new();
+        assert!(buffer.read_qname(&mut name).is_err());
+    }
+
+    #[test]
+    fn vector_packet_buffer_grows_past_512_bytes() {
+        let mut buffer = VectorPacketBuffer::new();
+        for _ in 0..600 {
+            buffer.write_u8(0x41).unwrap();
+        }
+
+        assert_eq!(buffer.pos(), 600);
+        assert_eq!(buffer.get(599).unwrap(), 0x41);
+    }
+
+    #[test]
+    fn write_qname_unicode_encodes_and_round_trips() {
+        let mut buffer = BytePacketBuffer::new();
+        buffer.write_qname_unicode("müller.de").unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut ascii = String::new();
+        buffer.read_qname(&mut ascii).unwrap();
+        assert_eq!(ascii, "xn--mller-kva.de");
+
+        buffer.seek(0).unwrap();
+        let mut unicode = String::new();
+        buffer.read_qname_unicode(&mut unicode).unwrap();
+        assert_eq!(unicode, "müller.de");
+    }
+
+    #[test]
+    fn write_qname_unicode_passes_through_existing_xn_form() {
+        let mut buffer = BytePacketBuffer::new();
+        buffer.write_qname_unicode("xn--mller-kva.de").unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut ascii = String::new();
+        buffer.read_qname(&mut ascii).unwrap();
+        assert_eq!(ascii, "xn--mller-kva.de");
+    }
+
+    #[test]
+    fn write_qname_unicode_mixed_script_round_trips() {
+        let mut buffer = BytePacketBuffer::new();
+        buffer.write_qname_unicode("例え.jp").unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut unicode = String::new();
+        buffer.read_qname_unicode(&mut unicode).unwrap();
+        assert_eq!(unicode, "例え.jp");
+    }
+
+    #[test]
+    fn vector_packet_buffer_round_trips_qname() {
+        let mut buffer = VectorPacketBuffer::new();
+        buffer.write_qname("www.example.com").unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut name = String::new();
+        buffer.read_qname(&mut name).unwrap();
+        assert_eq!(name, "www.example.com");
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -223,12 +605,15 @@ impl DnsPacket {
         }
     }
 
-    pub fn from_buffer(buffer: &mut BytePacketBuffer) -> super::Result<DnsPacket> {
+    pub fn from_buffer(buffer: &mut dyn PacketBuffer) -> super::Result<DnsPacket> {
         let mut result = DnsPacket::new();
         result.header.read(buffer)?;
 
         for _ in 0..result.header.questions {
-            let mut question = super::question::DnsQuestion::new("".to_string(), super::query::QueryType::UNKNOWN(0));
+            let mut question = super::question::DnsQuestion::new(
+                "".to_string(),
+                super::query::QueryType::UNKNOWN(0),
+            );
             question.read(buffer)?;
             result.questions.push(question);
         }
@@ -249,7 +634,7 @@ impl DnsPacket {
         Ok(result)
     }
 
-    pub fn write(&mut self, buffer: &mut BytePacketBuffer) -> super::Result<()> {
+    pub fn write(&mut self, buffer: &mut dyn PacketBuffer) -> super::Result<()> {
         self.header.questions = self.questions.len() as u16;
         self.header.answers = self.answers.len() as u16;
         self.header.authoritative_entries = self.authorities.len() as u16;
@@ -272,4 +657,52 @@ impl DnsPacket {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    // get_random_a returns the address of the first A record in the
+    // answers section, if any. Used once a lookup has actually resolved
+    // the query rather than handed back a referral.
+    pub fn get_random_a(&self) -> Option<std::net::Ipv4Addr> {
+        self.answers.iter().find_map(|record| match record {
+            super::record::DnsRecord::A { addr, .. } => Some(*addr),
+            _ => None,
+        })
+    }
+
+    // get_resolved_ns looks through the authorities section for an NS
+    // record covering `qname`, then checks whether the resources
+    // ("glue") section already carries that nameserver's A record, so the
+    // resolver can jump straight to it without a further lookup.
+    pub fn get_resolved_ns(&self, qname: &str) -> Option<std::net::Ipv4Addr> {
+        self.authorities
+            .iter()
+            .filter_map(|record| match record {
+                super::record::DnsRecord::NS { domain, host, .. } => Some((domain, host)),
+                _ => None,
+            })
+            .filter(|(domain, _)| qname.ends_with(domain.as_str()))
+            .filter_map(|(_, host)| {
+                self.resources.iter().find_map(|record| match record {
+                    super::record::DnsRecord::A { domain, addr, .. } if domain == host => {
+                        Some(*addr)
+                    }
+                    _ => None,
+                })
+            })
+            .next()
+    }
+
+    // get_unresolved_ns is get_resolved_ns's fallback: it returns the
+    // hostname of an NS record covering `qname` when no glue record was
+    // provided, so the caller can resolve that hostname itself.
+    pub fn get_unresolved_ns(&self, qname: &str) -> Option<String> {
+        self.authorities
+            .iter()
+            .filter_map(|record| match record {
+                super::record::DnsRecord::NS { domain, host, .. } => Some((domain, host)),
+                _ => None,
+            })
+            .filter(|(domain, _)| qname.ends_with(domain.as_str()))
+            .map(|(_, host)| host.clone())
+            .next()
+    }
+}
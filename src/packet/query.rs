@@ -0,0 +1,38 @@
+// QueryType mirrors the 16-bit TYPE field used in both questions and
+// records. UNKNOWN carries through any value we don't have a name for
+// instead of silently dropping it.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum QueryType {
+    UNKNOWN(u16),
+    A,
+    NS,
+    CNAME,
+    MX,
+    AAAA,
+}
+
+impl QueryType {
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_num(&self) -> u16 {
+        match *self {
+            QueryType::UNKNOWN(x) => x,
+            QueryType::A => 1,
+            QueryType::NS => 2,
+            QueryType::CNAME => 5,
+            QueryType::MX => 15,
+            QueryType::AAAA => 28,
+        }
+    }
+
+    pub fn from_num(num: u16) -> QueryType {
+        match num {
+            1 => QueryType::A,
+            2 => QueryType::NS,
+            5 => QueryType::CNAME,
+            15 => QueryType::MX,
+            28 => QueryType::AAAA,
+            _ => QueryType::UNKNOWN(num),
+        }
+    }
+}
@@ -6,21 +6,23 @@ pub struct DnsQuestion {
 
 impl DnsQuestion {
     pub fn new(name: String, qtype: super::query::QueryType) -> DnsQuestion {
-        DnsQuestion {
-            name: name,
-            qtype: qtype,
-        }
+        DnsQuestion { name, qtype }
     }
 
-    pub fn read(&mut self, buffer: &mut super::packet::BytePacketBuffer) -> super::Result<()> {
+    pub fn read(&mut self, buffer: &mut dyn super::packet::PacketBuffer) -> super::Result<()> {
         buffer.read_qname(&mut self.name)?;
         self.qtype = super::query::QueryType::from_num(buffer.read_u16()?);
         let _ = buffer.read_u16()?;
         Ok(())
     }
 
-    pub fn write(&self, buffer: &mut super::packet::BytePacketBuffer) -> super::Result<()> {
-        buffer.write_qname(&self.name)?;
+    pub fn write(&self, buffer: &mut dyn super::packet::PacketBuffer) -> super::Result<()> {
+        // Names handed to us by callers are user-facing and may contain
+        // non-ASCII characters (e.g. "müller.de"), so go through the
+        // IDNA-aware encoder rather than plain write_qname - otherwise a
+        // Unicode name would go out on the wire as raw UTF-8 and never
+        // resolve.
+        buffer.write_qname_unicode(&self.name)?;
 
         let typenum = self.qtype.to_num();
         buffer.write_u16(typenum)?;
@@ -29,3 +31,22 @@ impl DnsQuestion {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::packet::{BytePacketBuffer, PacketBuffer};
+    use crate::packet::query::QueryType;
+
+    #[test]
+    fn write_encodes_unicode_name_to_punycode() {
+        let mut buffer = BytePacketBuffer::new();
+        let question = DnsQuestion::new("müller.de".to_string(), QueryType::A);
+        question.write(&mut buffer).unwrap();
+
+        buffer.seek(0).unwrap();
+        let mut name = String::new();
+        buffer.read_qname(&mut name).unwrap();
+        assert_eq!(name, "xn--mller-kva.de");
+    }
+}
@@ -0,0 +1,41 @@
+// ResultCode mirrors the 4-bit RCODE field in the DNS header (RFC 1035
+// section 4.1.1). UNKNOWN carries through any value we don't have a name
+// for instead of silently clamping it to NOERROR.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResultCode {
+    NOERROR,
+    FORMERR,
+    SERVFAIL,
+    NXDOMAIN,
+    NOTIMP,
+    REFUSED,
+    UNKNOWN(u8),
+}
+
+impl ResultCode {
+    pub fn from_num(num: u8) -> ResultCode {
+        match num {
+            0 => ResultCode::NOERROR,
+            1 => ResultCode::FORMERR,
+            2 => ResultCode::SERVFAIL,
+            3 => ResultCode::NXDOMAIN,
+            4 => ResultCode::NOTIMP,
+            5 => ResultCode::REFUSED,
+            num => ResultCode::UNKNOWN(num),
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_num(&self) -> u8 {
+        match *self {
+            ResultCode::NOERROR => 0,
+            ResultCode::FORMERR => 1,
+            ResultCode::SERVFAIL => 2,
+            ResultCode::NXDOMAIN => 3,
+            ResultCode::NOTIMP => 4,
+            ResultCode::REFUSED => 5,
+            ResultCode::UNKNOWN(num) => num,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// A small iterative recursive resolver: starting from the root servers,
+// follow NS referrals down the delegation chain ourselves instead of
+// forwarding the question to a single upstream and trusting it to recurse.
+
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::time::Duration;
+
+use crate::packet::packet::{BytePacketBuffer, DnsPacket};
+use crate::packet::query::QueryType;
+use crate::packet::question::DnsQuestion;
+use crate::packet::result::ResultCode;
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = std::result::Result<T, Error>;
+
+// How long to wait for any single server to answer before giving up on it.
+// Without this, a root or authority server that's down or filtering us
+// blocks the whole resolve() call - and the server loop that calls it -
+// forever.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+// The 13 root server IPs (a.root-servers.net through m.root-servers.net).
+const ROOT_SERVERS: [Ipv4Addr; 13] = [
+    Ipv4Addr::new(198, 41, 0, 4),
+    Ipv4Addr::new(199, 9, 14, 201),
+    Ipv4Addr::new(192, 33, 4, 12),
+    Ipv4Addr::new(199, 7, 91, 13),
+    Ipv4Addr::new(192, 203, 230, 10),
+    Ipv4Addr::new(192, 5, 5, 241),
+    Ipv4Addr::new(192, 112, 36, 4),
+    Ipv4Addr::new(198, 97, 190, 53),
+    Ipv4Addr::new(192, 36, 148, 17),
+    Ipv4Addr::new(192, 58, 128, 30),
+    Ipv4Addr::new(193, 0, 14, 129),
+    Ipv4Addr::new(199, 7, 83, 42),
+    Ipv4Addr::new(202, 12, 27, 33),
+];
+
+// Bail out after this many hops down the delegation chain, so a referral
+// loop can't spin us forever.
+const MAX_HOPS: usize = 20;
+
+// resolve performs the query itself, starting from the root servers and
+// following NS referrals until an answer (or NXDOMAIN) comes back.
+pub fn resolve(qname: &str, qtype: QueryType) -> Result<DnsPacket> {
+    let mut ns = IpAddr::V4(ROOT_SERVERS[0]);
+
+    for _ in 0..MAX_HOPS {
+        // A server that never answers shouldn't wedge the whole
+        // resolution - give up on this chain and surface SERVFAIL rather
+        // than hang or bubble the timeout up as a hard error.
+        let response = match lookup(qname, qtype, ns) {
+            Ok(response) => response,
+            Err(_) => return Ok(servfail(qname, qtype)),
+        };
+
+        // Got a real answer, or an authoritative "no such domain" - either
+        // way, we're done.
+        if (!response.answers.is_empty() && response.header.rescode == ResultCode::NOERROR)
+            || response.header.rescode == ResultCode::NXDOMAIN
+        {
+            return Ok(response);
+        }
+
+        // The authority section pointed us at the next nameserver and
+        // handed us its glue A record in the same response - follow it.
+        if let Some(new_ns) = response.get_resolved_ns(qname) {
+            ns = IpAddr::V4(new_ns);
+            continue;
+        }
+
+        // No glue record, so we only know the next nameserver's hostname.
+        // Resolve that hostname (a fresh recursion starting at the root
+        // again) before we can continue.
+        let new_ns_name = match response.get_unresolved_ns(qname) {
+            Some(name) => name,
+            None => return Ok(servfail(qname, qtype)),
+        };
+
+        let recursive_response = resolve(&new_ns_name, QueryType::A)?;
+        let new_ns = match recursive_response.get_random_a() {
+            Some(addr) => addr,
+            None => return Ok(servfail(qname, qtype)),
+        };
+
+        ns = IpAddr::V4(new_ns);
+    }
+
+    Ok(servfail(qname, qtype))
+}
+
+// lookup sends a single, non-recursive question to `server` and returns
+// whatever it hands back.
+fn lookup(qname: &str, qtype: QueryType, server: IpAddr) -> Result<DnsPacket> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket.set_read_timeout(Some(QUERY_TIMEOUT))?;
+    socket.set_write_timeout(Some(QUERY_TIMEOUT))?;
+
+    let mut packet = DnsPacket::new();
+    packet.header.id = 6666;
+    packet.header.questions = 1;
+    packet.header.recursion_desired = false;
+    packet
+        .questions
+        .push(DnsQuestion::new(qname.to_string(), qtype));
+
+    let mut req_buffer = BytePacketBuffer::new();
+    packet.write(&mut req_buffer)?;
+    socket.send_to(&req_buffer.buf[0..req_buffer.pos], (server, 53))?;
+
+    let mut res_buffer = BytePacketBuffer::new();
+    socket.recv_from(&mut res_buffer.buf)?;
+
+    DnsPacket::from_buffer(&mut res_buffer)
+}
+
+// servfail builds the packet we hand back when no usable nameserver can
+// be found anywhere along the chain.
+fn servfail(qname: &str, qtype: QueryType) -> DnsPacket {
+    let mut packet = DnsPacket::new();
+    packet.header.rescode = ResultCode::SERVFAIL;
+    packet
+        .questions
+        .push(DnsQuestion::new(qname.to_string(), qtype));
+    packet
+}
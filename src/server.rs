@@ -0,0 +1,70 @@
+// The other half of the crate: instead of only sending queries out, answer
+// incoming ones. Binds a UDP socket, parses whatever shows up, resolves it
+// (recursing from the root servers), and writes a reply back to the client.
+
+use std::net::UdpSocket;
+
+use crate::packet::packet::{BytePacketBuffer, DnsPacket, PacketBuffer};
+use crate::packet::result::ResultCode;
+use crate::resolve;
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = std::result::Result<T, Error>;
+
+// run binds port 53 (or whatever `port` is, handy for testing without
+// root) and answers queries until the process is killed. A malformed
+// packet is logged and skipped, and a failed lookup is answered with
+// SERVFAIL, rather than either one bringing the loop down.
+pub fn run(port: u16) -> Result<()> {
+    let socket = UdpSocket::bind(("0.0.0.0", port))?;
+
+    loop {
+        if let Err(e) = handle_query(&socket) {
+            eprintln!("failed to handle query: {}", e);
+        }
+    }
+}
+
+fn handle_query(socket: &UdpSocket) -> Result<()> {
+    let mut req_buffer = BytePacketBuffer::new();
+    let (_, src) = socket.recv_from(&mut req_buffer.buf)?;
+
+    let request = DnsPacket::from_buffer(&mut req_buffer)?;
+
+    let mut response = DnsPacket::new();
+    response.header.id = request.header.id;
+    response.header.recursion_desired = request.header.recursion_desired;
+    response.header.recursion_available = true;
+    response.header.response = true;
+
+    match request.questions.first() {
+        Some(question) => {
+            response.questions.push(question.clone());
+
+            match resolve::resolve(&question.name, question.qtype) {
+                Ok(result) => {
+                    response.header.rescode = result.header.rescode;
+
+                    response.answers.extend(result.answers);
+                    response.authorities.extend(result.authorities);
+                    response.resources.extend(result.resources);
+                }
+                Err(_) => {
+                    response.header.rescode = ResultCode::SERVFAIL;
+                }
+            }
+        }
+        None => {
+            response.header.rescode = ResultCode::FORMERR;
+        }
+    }
+
+    let mut res_buffer = BytePacketBuffer::new();
+    response.write(&mut res_buffer)?;
+
+    let len = res_buffer.pos();
+    let data = res_buffer.get_range(0, len)?;
+    socket.send_to(data, src)?;
+
+    Ok(())
+}